@@ -4,6 +4,7 @@
 
 use sha3x_miner::core::types::MiningJob;
 use sha3x_miner::miner::gpu::opencl::{OpenClDevice, OpenClEngine};
+use sha3x_miner::miner::nonce::NonceAllocator;
 use std::time::Instant;
 use tracing::{info, error};
 
@@ -58,32 +59,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("├─ Algorithm: {:?}", test_job.algo);
     info!("└─ XN (extra nonce): {}", test_job.extranonce2.as_ref().unwrap_or(&"None".to_string()));
     
-    let batch_size = engine.get_suggested_batch_size();
+    let batch_size = engine.get_suggested_batch_size(test_job.algo);
     info!("🔧 Batch size: {}", batch_size);
     
     // Run mining test for 10 seconds
     let test_duration = std::time::Duration::from_secs(10);
     let start_time = Instant::now();
     let mut total_hashes = 0u64;
-    let mut nonce_offset = 0u64;
+    let nonce_allocator = NonceAllocator::for_job(&test_job);
     let mut iteration = 0;
-    
+
     info!("🚀 Starting REAL GPU mining test for 10 seconds...");
-    
+
     while start_time.elapsed() < test_duration {
         iteration += 1;
-        
-        match engine.mine(&test_job, nonce_offset, batch_size).await {
-            Ok((found_nonce, hashes_processed, best_difficulty)) => {
-                total_hashes += hashes_processed as u64;
-                
-                if let Some(nonce) = found_nonce {
-                    info!("🎉 FOUND SHARE! Nonce: {:016x}, Difficulty: {}", 
-                          nonce, best_difficulty);
+
+        match engine.mine(&test_job, &nonce_allocator, batch_size).await {
+            Ok(outcome) => {
+                total_hashes += outcome.hashes_processed as u64;
+
+                if let (Some(nonce), Some(validation)) = (outcome.found_nonce, outcome.validation) {
+                    info!("🎉 FOUND SHARE! Nonce: {:016x}, Difficulty: {}, meets pool share: {}, meets network target: {}",
+                          nonce, validation.difficulty.as_u64(), validation.meets_pool_share, validation.meets_network_target);
+                }
+
+                if outcome.hashes_processed == 0 {
+                    info!("🔁 Nonce space exhausted for job {} — would request a new job here", test_job.job_id);
+                    break;
                 }
-                
-                nonce_offset += hashes_processed as u64;
-                
+
                 // Progress update every 50 iterations
                 if iteration % 50 == 0 {
                     let elapsed = start_time.elapsed().as_secs_f64();
@@ -144,14 +148,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Test a few iterations with XN
     info!("🔧 Testing XN nonce format (simulating LuckyPool):");
+    let luckypool_nonce_allocator = NonceAllocator::for_job(&luckypool_test_job);
     for i in 0..3 {
-        match engine.mine(&luckypool_test_job, i * 100000, 1000).await {
-            Ok((found_nonce, hashes_processed, best_difficulty)) => {
-                if let Some(nonce) = found_nonce {
+        match engine.mine(&luckypool_test_job, &luckypool_nonce_allocator, 1000).await {
+            Ok(outcome) => {
+                if let Some(nonce) = outcome.found_nonce {
                     // This would be formatted with XN in the actual manager
                     info!("├─ Found nonce: {:016x} (would be formatted as XN + 6 bytes for LuckyPool)", nonce);
                 }
-                info!("├─ Test {}: {} hashes, best difficulty: {}", i + 1, hashes_processed, best_difficulty);
+                let difficulty = outcome.validation.map(|v| v.difficulty.as_u64()).unwrap_or(0);
+                info!("├─ Test {}: {} hashes, best difficulty: {}", i + 1, outcome.hashes_processed, difficulty);
             }
             Err(e) => {
                 error!("├─ XN test error: {}", e);
@@ -0,0 +1,5 @@
+// File: src/core/mod.rs
+
+//! Core data types shared across miner backends (CPU, GPU, pool/solo modes).
+
+pub mod types;
@@ -0,0 +1,188 @@
+// File: src/core/types.rs
+
+//! Shared job and algorithm types passed between the pool/solo client and the
+//! mining engines.
+
+/// Proof-of-work algorithm a [`MiningJob`] was issued for.
+///
+/// `OpenClEngine` keeps one compiled kernel per variant it has been asked to
+/// support and dispatches on `MiningJob::algo` rather than assuming SHA3x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// Tari's RandomX-free SHA3(SHA3(input)) proof of work.
+    Sha3x,
+    /// Double SHA-256, for pools that switch the engine to a SHA-256d coin.
+    Sha256d,
+}
+
+impl Algorithm {
+    /// Every algorithm the engine knows how to build a kernel for.
+    pub const ALL: [Algorithm; 2] = [Algorithm::Sha3x, Algorithm::Sha256d];
+
+    /// OpenCL kernel source file (relative to the `kernels/` directory) used
+    /// to build this algorithm's program.
+    pub fn kernel_source_name(&self) -> &'static str {
+        match self {
+            Algorithm::Sha3x => "sha3x.cl",
+            Algorithm::Sha256d => "sha256d.cl",
+        }
+    }
+
+    /// Name of the `__kernel` entry point within [`Self::kernel_source_name`].
+    pub fn kernel_fn_name(&self) -> &'static str {
+        match self {
+            Algorithm::Sha3x => "sha3x_search",
+            Algorithm::Sha256d => "sha256d_search",
+        }
+    }
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Sha3x
+    }
+}
+
+/// A unit of work handed down by a pool (stratum) or solo node.
+///
+/// Fields beyond `job_id`/`mining_hash`/`target_difficulty`/`height`/`algo`
+/// are `Option` because they only apply to some upstreams: `extranonce2` is
+/// the "XN" field some pools (e.g. LuckyPool) use to partition the nonce
+/// space across miners, while `prev_hash`..`target` mirror the fields a
+/// Stratum `mining.notify`/`mining.set_target` pair carries for pools that
+/// validate shares against a real block template rather than a flat
+/// difficulty.
+#[derive(Debug, Clone)]
+pub struct MiningJob {
+    pub job_id: String,
+    pub mining_hash: Vec<u8>,
+    pub target_difficulty: u64,
+    pub height: u64,
+    pub algo: Algorithm,
+    /// Pool-assigned extra nonce (hex string), e.g. LuckyPool's "XN". When
+    /// present, the miner only owns the low 6 bytes of the nonce and must
+    /// not touch the bytes the pool prefixed.
+    pub extranonce2: Option<String>,
+    pub prev_hash: Option<String>,
+    pub merkle_root: Option<String>,
+    pub version: Option<u32>,
+    pub ntime: Option<u32>,
+    pub nbits: Option<u32>,
+    pub merkle_path: Option<Vec<String>>,
+    /// Full 256-bit network block target, big-endian, when known.
+    pub target: Option<[u8; 32]>,
+}
+
+impl MiningJob {
+    /// Whether this job carries a pool extranonce, meaning the miner must
+    /// restrict itself to a narrower nonce range than the full `u64`.
+    pub fn has_extranonce(&self) -> bool {
+        self.extranonce2.is_some()
+    }
+}
+
+/// Mining difficulty: how many average hashes it takes to find a hash at or
+/// below the corresponding target. Wraps a `u64` with checked construction
+/// so a zero difficulty (meaningless — every hash would "meet" it) or an
+/// overflowing conversion can't silently become a bogus value, the way a
+/// raw `u64` comparison against Tari's 256-bit targets invites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Difficulty(u64);
+
+#[derive(Debug, thiserror::Error)]
+pub enum DifficultyError {
+    #[error("difficulty must be non-zero")]
+    Zero,
+}
+
+impl Difficulty {
+    /// The lowest valid difficulty.
+    pub const MIN: Difficulty = Difficulty(1);
+
+    /// Construct from a raw value, rejecting zero (a zero difficulty would
+    /// make every hash "meet" it, which is never the intent of a target).
+    pub fn new(value: u64) -> Result<Self, DifficultyError> {
+        if value == 0 {
+            return Err(DifficultyError::Zero);
+        }
+        Ok(Difficulty(value))
+    }
+
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Difficulty implied by a 256-bit big-endian hash target, mirroring
+    /// Tari's proof-of-work difficulty rule (`difficulty ≈ MAX / target`).
+    /// Saturates at `u64::MAX` for an all-zero target instead of dividing
+    /// by zero, and truncates the target to its leading 8 bytes — the
+    /// bytes that dominate the ratio for any target in a realistic
+    /// difficulty range.
+    pub fn from_target(target: &[u8; 32]) -> Difficulty {
+        if target.iter().all(|&b| b == 0) {
+            return Difficulty(u64::MAX);
+        }
+        let mut leading = [0u8; 8];
+        leading.copy_from_slice(&target[0..8]);
+        let target_approx = u64::from_be_bytes(leading).max(1);
+        Difficulty(u64::MAX.saturating_div(target_approx).max(1))
+    }
+
+    /// Inverse of [`Self::from_target`]: the 256-bit big-endian target this
+    /// difficulty corresponds to (same leading-8-bytes approximation,
+    /// zero-padded in the remaining bytes).
+    pub fn to_target(self) -> [u8; 32] {
+        let target_approx = u64::MAX.saturating_div(self.0.max(1));
+        let mut target = [0u8; 32];
+        target[0..8].copy_from_slice(&target_approx.to_be_bytes());
+        target
+    }
+
+    /// Whether a hash at this difficulty also meets `threshold` (i.e. is at
+    /// least as hard to find).
+    pub fn meets(self, threshold: Difficulty) -> bool {
+        self >= threshold
+    }
+}
+
+/// Which difficulty thresholds a mined nonce's hash met, so the caller can
+/// submit a pool share and a network block solution separately rather than
+/// conflating the two.
+#[derive(Debug, Clone, Copy)]
+pub struct ShareValidation {
+    pub difficulty: Difficulty,
+    pub meets_pool_share: bool,
+    pub meets_network_target: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_zero_difficulty() {
+        assert!(matches!(Difficulty::new(0), Err(DifficultyError::Zero)));
+    }
+
+    #[test]
+    fn from_target_is_roughly_invertible() {
+        let difficulty = Difficulty::new(1_000_000).unwrap();
+        let target = difficulty.to_target();
+        let recovered = Difficulty::from_target(&target);
+        assert_eq!(recovered, difficulty);
+    }
+
+    #[test]
+    fn all_zero_target_saturates_instead_of_dividing_by_zero() {
+        assert_eq!(Difficulty::from_target(&[0u8; 32]), Difficulty(u64::MAX));
+    }
+
+    #[test]
+    fn meets_is_reflexive_and_monotonic() {
+        let low = Difficulty::new(100).unwrap();
+        let high = Difficulty::new(200).unwrap();
+        assert!(low.meets(low));
+        assert!(high.meets(low));
+        assert!(!low.meets(high));
+    }
+}
@@ -0,0 +1,7 @@
+// File: src/lib.rs
+
+//! sha3x-miner: a GPU/CPU miner for Tari (SHA3x) and compatible pools.
+
+pub mod core;
+pub mod miner;
+pub mod stats;
@@ -0,0 +1,192 @@
+// File: src/miner/gpu/benchmark.rs
+
+//! Auto-tuning benchmark mode: sweeps batch size and OpenCL local worksize
+//! for a device/algorithm pair via a hill-climb, converging on the
+//! combination with peak sustained throughput, then persists the winner so
+//! later mining runs skip re-tuning.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::core::types::MiningJob;
+use crate::miner::gpu::opencl::{EngineError, OpenClEngine};
+use crate::miner::nonce::NonceAllocator;
+
+/// Whether an engine is currently sweeping settings or running a live
+/// mining job, so logs/UIs can't mistake a benchmark run's throughput
+/// numbers for real share-producing hashrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineMode {
+    Benchmark,
+    Mining,
+}
+
+/// Local worksizes tried during a sweep. `None` lets the OpenCL driver pick.
+const WORKSIZE_CANDIDATES: [Option<usize>; 4] = [None, Some(64), Some(128), Some(256)];
+
+/// How long to run each candidate before reading its throughput.
+const SWEEP_RUN_DURATION: Duration = Duration::from_secs(2);
+
+/// Batch size/worksize that gave the best throughput for one device+algorithm.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TunedSettings {
+    pub batch_size: u32,
+    pub local_worksize: Option<usize>,
+    pub mhs: f64,
+}
+
+/// Per-device tuned settings, persisted to disk so mining runs after the
+/// first skip re-tuning.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TuningStore {
+    /// Keyed by `"{device_id}:{algo:?}"`.
+    settings: HashMap<String, TunedSettings>,
+}
+
+impl TuningStore {
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let raw = serde_json::to_string_pretty(self).unwrap_or_default();
+        fs::write(path, raw)
+    }
+
+    fn key(device_id: &str, job: &MiningJob) -> String {
+        format!("{device_id}:{:?}", job.algo)
+    }
+
+    pub fn get(&self, device_id: &str, job: &MiningJob) -> Option<TunedSettings> {
+        self.settings.get(&Self::key(device_id, job)).copied()
+    }
+
+    pub fn set(&mut self, device_id: &str, job: &MiningJob, settings: TunedSettings) {
+        self.settings.insert(Self::key(device_id, job), settings);
+    }
+}
+
+/// Default location for persisted tuning results.
+pub fn default_tuning_path() -> PathBuf {
+    PathBuf::from("tuning.json")
+}
+
+/// Run the benchmark: for each worksize candidate, hill-climb batch size
+/// (start at the engine's suggested size, step up while throughput
+/// improves, back off and halve the step on a decrease or a kernel error),
+/// then return the best combination found across all worksizes.
+///
+/// Looks up `store` first and returns the cached result without re-running
+/// the sweep if `device_id`+`job.algo` already has one.
+pub async fn autotune(
+    engine: &mut OpenClEngine,
+    job: &MiningJob,
+    device_id: &str,
+    store: &mut TuningStore,
+) -> Result<TunedSettings, EngineError> {
+    if let Some(cached) = store.get(device_id, job) {
+        info!("🔧 Benchmark: using cached tuning for {device_id} ({:?}): {cached:?}", job.algo);
+        return Ok(cached);
+    }
+
+    engine.set_mode(EngineMode::Benchmark);
+    let result = run_sweep(engine, job, device_id, store).await;
+    engine.set_mode(EngineMode::Mining);
+    result
+}
+
+async fn run_sweep(
+    engine: &mut OpenClEngine,
+    job: &MiningJob,
+    device_id: &str,
+    store: &mut TuningStore,
+) -> Result<TunedSettings, EngineError> {
+    let allocator = NonceAllocator::for_job(job);
+    let suggested = engine.get_suggested_batch_size(job.algo);
+
+    let mut overall_best: Option<TunedSettings> = None;
+    for worksize in WORKSIZE_CANDIDATES {
+        let best = hill_climb_batch_size(engine, job, &allocator, suggested, worksize).await?;
+        info!(
+            "🔧 Benchmark: worksize {:?} peaked at {:.2} MH/s (batch_size={})",
+            worksize, best.mhs, best.batch_size
+        );
+        if overall_best.as_ref().map_or(true, |b| best.mhs > b.mhs) {
+            overall_best = Some(best);
+        }
+    }
+
+    let best = overall_best.expect("WORKSIZE_CANDIDATES is non-empty");
+    info!(
+        "✅ Benchmark converged for {device_id}: batch_size={} local_worksize={:?} ({:.2} MH/s peak)",
+        best.batch_size, best.local_worksize, best.mhs
+    );
+    store.set(device_id, job, best);
+    Ok(best)
+}
+
+async fn hill_climb_batch_size(
+    engine: &mut OpenClEngine,
+    job: &MiningJob,
+    allocator: &NonceAllocator,
+    start: u32,
+    local_worksize: Option<usize>,
+) -> Result<TunedSettings, EngineError> {
+    let mut best = measure(engine, job, allocator, start, local_worksize).await?;
+    let mut current = start;
+    let mut step = (start / 2).max(1);
+
+    while step > 0 {
+        let candidate_size = current + step;
+        match measure(engine, job, allocator, candidate_size, local_worksize).await {
+            Ok(candidate) if candidate.mhs > best.mhs => {
+                current = candidate_size;
+                best = candidate;
+            }
+            // No improvement, or the candidate errored (e.g. a kernel/allocation
+            // failure from too large a batch): back off and try a finer step.
+            _ => step /= 2,
+        }
+    }
+
+    Ok(best)
+}
+
+async fn measure(
+    engine: &mut OpenClEngine,
+    job: &MiningJob,
+    allocator: &NonceAllocator,
+    batch_size: u32,
+    local_worksize: Option<usize>,
+) -> Result<TunedSettings, EngineError> {
+    allocator.reset();
+    let started = Instant::now();
+    let mut hashes = 0u64;
+
+    while started.elapsed() < SWEEP_RUN_DURATION {
+        let outcome = engine
+            .mine_with_worksize(job, allocator, batch_size, local_worksize)
+            .await?;
+        if outcome.hashes_processed == 0 {
+            // Ran out of nonce space mid-sweep; reset and keep measuring.
+            allocator.reset();
+            continue;
+        }
+        hashes += outcome.hashes_processed as u64;
+    }
+
+    let mhs = hashes as f64 / started.elapsed().as_secs_f64() / 1_000_000.0;
+    Ok(TunedSettings {
+        batch_size,
+        local_worksize,
+        mhs,
+    })
+}
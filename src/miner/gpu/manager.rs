@@ -0,0 +1,405 @@
+// File: src/miner/gpu/manager.rs
+
+//! Multi-GPU orchestration: one [`OpenClEngine`] per detected device, all
+//! run concurrently against the same [`MiningJob`], with per-device config
+//! keyed by a stable hardware identifier ([`OpenClDevice::stable_id`])
+//! rather than enumeration index, since index order changes across
+//! reboots.
+//!
+//! Each device's loop is guarded by a [`Watchdog`] and watches for job
+//! replacement: after [`GpuManager::replace_job`], the device switches to
+//! the new job and its fresh nonce allocator before starting its next
+//! batch. `mine()` waits on its OpenCL events synchronously and never
+//! yields, so a batch already dispatched is not cancelled: it runs to
+//! completion and its result is handled under the old job first.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::core::types::{Difficulty, MiningJob};
+use crate::miner::gpu::benchmark::{autotune, default_tuning_path, TunedSettings, TuningStore};
+use crate::miner::gpu::opencl::{EngineError, OpenClDevice, OpenClEngine};
+use crate::miner::gpu::watchdog::{DeviceHealth, HealthState, Watchdog};
+use crate::miner::nonce::NonceAllocator;
+use crate::stats::{RejectionReason, Stats};
+
+/// Per-device overrides, keyed by [`OpenClDevice::stable_id`].
+#[derive(Debug, Clone)]
+pub struct DeviceConfig {
+    pub batch_size: Option<u32>,
+    pub intensity: Option<f32>,
+    pub enabled: bool,
+}
+
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: None,
+            intensity: None,
+            enabled: true,
+        }
+    }
+}
+
+/// A pool share a device found while mining (met `target_difficulty` but
+/// not necessarily the network block target).
+#[derive(Debug, Clone)]
+pub struct FoundShare {
+    pub device_id: String,
+    pub job_id: String,
+    pub nonce: u64,
+    pub difficulty: Difficulty,
+}
+
+/// A nonce that met the network block target, submitted separately from
+/// (and in addition to) ordinary pool shares.
+#[derive(Debug, Clone)]
+pub struct BlockSolution {
+    pub device_id: String,
+    pub job_id: String,
+    pub nonce: u64,
+    pub difficulty: Difficulty,
+}
+
+/// A job and the single nonce allocator every device mining it shares, so N
+/// GPUs assigned the same job partition one nonce stream instead of each
+/// starting its own counter at zero and re-scanning the same range.
+struct ActiveJob {
+    job: Arc<MiningJob>,
+    allocator: Arc<NonceAllocator>,
+}
+
+impl ActiveJob {
+    fn new(job: &MiningJob) -> Self {
+        Self {
+            job: Arc::new(job.clone()),
+            allocator: Arc::new(NonceAllocator::for_job(job)),
+        }
+    }
+}
+
+struct ManagedDevice {
+    device: OpenClDevice,
+    hashes: Arc<AtomicU64>,
+    health: Arc<HealthState>,
+    job_tx: watch::Sender<Arc<ActiveJob>>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+/// Owns one [`OpenClEngine`] per device and mines the same job across all
+/// of them concurrently, aggregating hashrate and forwarding shares onto a
+/// single channel.
+pub struct GpuManager {
+    devices: HashMap<String, ManagedDevice>,
+    configs: HashMap<String, DeviceConfig>,
+    shares_tx: mpsc::UnboundedSender<FoundShare>,
+    shares_rx: mpsc::UnboundedReceiver<FoundShare>,
+    blocks_tx: mpsc::UnboundedSender<BlockSolution>,
+    blocks_rx: mpsc::UnboundedReceiver<BlockSolution>,
+    stats: Stats,
+    tuning: TuningStore,
+    /// The job (and its shared nonce allocator) every currently-running
+    /// device is mining. `None` until the first device is added.
+    active_job: Option<Arc<ActiveJob>>,
+}
+
+impl GpuManager {
+    pub fn new() -> Self {
+        let (shares_tx, shares_rx) = mpsc::unbounded_channel();
+        let (blocks_tx, blocks_rx) = mpsc::unbounded_channel();
+        Self {
+            devices: HashMap::new(),
+            configs: HashMap::new(),
+            shares_tx,
+            shares_rx,
+            blocks_tx,
+            blocks_rx,
+            stats: Stats::new(),
+            tuning: TuningStore::load(default_tuning_path()),
+            active_job: None,
+        }
+    }
+
+    /// Shared handle to this manager's telemetry — snapshot it directly, or
+    /// hand it to [`crate::stats::http::spawn`] for a pollable JSON endpoint.
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
+    /// Set (or replace) the config for a device identified by its stable
+    /// id, ahead of it being added. Lets users pin a per-device batch size
+    /// or exclude a device (e.g. the display-attached GPU) before it's even
+    /// been detected this run.
+    pub fn set_device_config(&mut self, device_id: impl Into<String>, config: DeviceConfig) {
+        self.configs.insert(device_id.into(), config);
+    }
+
+    /// Detect every device and start mining `job` on each one whose config
+    /// doesn't mark it disabled.
+    pub async fn start(&mut self, job: &MiningJob) -> Result<(), EngineError> {
+        for device in OpenClDevice::detect_devices()? {
+            self.add_device(device, job).await?;
+        }
+        Ok(())
+    }
+
+    /// Add a single device at runtime (e.g. hot-plug) and start mining
+    /// `job` on it, unless its config marks it disabled.
+    pub async fn add_device(&mut self, device: OpenClDevice, job: &MiningJob) -> Result<(), EngineError> {
+        let id = device.stable_id();
+        let config = self
+            .configs
+            .entry(id.clone())
+            .or_insert_with(DeviceConfig::default)
+            .clone();
+        if !config.enabled {
+            info!("⏭️  Skipping disabled device {id} ({})", device.info_string());
+            return Ok(());
+        }
+
+        let mut engine = OpenClEngine::new(device.clone());
+        engine.initialize()?;
+        // `initialize()` only compiles the Sha3x kernel; make sure this
+        // job's actual algorithm is loaded too, since pools can assign
+        // Sha256d straight off.
+        engine.load_algorithms(&[job.algo])?;
+        // Precedence: an explicit per-device override always wins, then a
+        // persisted auto-tuned result for this device+algorithm, then the
+        // engine's untuned suggestion.
+        let mut batch_size = config.batch_size.unwrap_or_else(|| {
+            self.tuning
+                .get(&id, job)
+                .map(|tuned| tuned.batch_size)
+                .unwrap_or_else(|| engine.get_suggested_batch_size(job.algo))
+        });
+        if let Some(intensity) = config.intensity {
+            batch_size = ((batch_size as f32 * intensity).round() as u32).max(1);
+        }
+
+        // Reuse the running job's shared allocator so this device partitions
+        // the same nonce stream as every other device already mining it,
+        // instead of starting its own counter at zero and re-scanning the
+        // range the others are already covering.
+        let active_job = match &self.active_job {
+            Some(active) if active.job.job_id == job.job_id => active.clone(),
+            _ => {
+                let active = Arc::new(ActiveJob::new(job));
+                self.active_job = Some(active.clone());
+                active
+            }
+        };
+
+        let watchdog = Watchdog::new(device.clone());
+        let health = watchdog.health();
+        let (job_tx, mut job_rx) = watch::channel(active_job);
+        let hashes = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let shares_tx = self.shares_tx.clone();
+        let blocks_tx = self.blocks_tx.clone();
+        let stats = self.stats.clone();
+        let hashes_for_task = hashes.clone();
+        let stop_for_task = stop.clone();
+        let device_id = id.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut engine = engine;
+            let mut watchdog = watchdog;
+            let initial = job_rx.borrow_and_update().clone();
+            let mut current_job = initial.job.clone();
+            let mut allocator = initial.allocator.clone();
+            drop(initial);
+
+            loop {
+                if stop_for_task.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                tokio::select! {
+                    biased;
+                    changed = job_rx.changed() => {
+                        if changed.is_err() {
+                            break; // GpuManager dropped, device is being removed
+                        }
+                        // Job replacement between batches: `biased` polls this
+                        // arm first, so a pending job change wins over starting
+                        // another batch. A batch already running is not
+                        // cancelled, since `mine()` does not yield mid-batch.
+                        let active = job_rx.borrow_and_update().clone();
+                        current_job = active.job.clone();
+                        allocator = active.allocator.clone();
+                        // Proactively compile the new job's kernel now, rather
+                        // than letting the next `mine()` fail with
+                        // `AlgorithmNotLoaded` and have the watchdog mistake a
+                        // routine pool algo switch for a hardware fault.
+                        if let Err(e) = engine.load_algorithms(&[current_job.algo]) {
+                            error!("❌ {device_id}: failed to load algorithm {:?} for new job: {e}", current_job.algo);
+                            break;
+                        }
+                        info!("🔀 {device_id}: switched to job {}", current_job.job_id);
+                    }
+                    result = watchdog.guarded_mine(&mut engine, &current_job, &allocator, batch_size) => {
+                        match result {
+                            Ok(outcome) => {
+                                if watchdog.health().get() == DeviceHealth::Dead {
+                                    error!("💀 {device_id}: device marked dead, stopping its task");
+                                    break;
+                                }
+                                if outcome.hashes_processed == 0 {
+                                    if outcome.recovering {
+                                        // The watchdog just rebuilt the engine; retry
+                                        // right away instead of waiting for a job
+                                        // change that may never come.
+                                        continue;
+                                    }
+                                    // Nonce space exhausted: wait for the next job
+                                    // instead of busy-looping.
+                                    if job_rx.changed().await.is_err() {
+                                        break;
+                                    }
+                                    let active = job_rx.borrow_and_update().clone();
+                                    current_job = active.job.clone();
+                                    allocator = active.allocator.clone();
+                                    if let Err(e) = engine.load_algorithms(&[current_job.algo]) {
+                                        error!("❌ {device_id}: failed to load algorithm {:?} for new job: {e}", current_job.algo);
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                hashes_for_task.fetch_add(outcome.hashes_processed as u64, Ordering::Relaxed);
+                                stats.record_hashes(&device_id, outcome.hashes_processed as u64);
+                                if let (Some(nonce), Some(validation)) = (outcome.found_nonce, outcome.validation) {
+                                    stats.record_difficulty(&device_id, validation.difficulty);
+                                    // A network-target hit is submitted as a block
+                                    // solution rather than (or in addition to) an
+                                    // ordinary pool share.
+                                    if validation.meets_network_target {
+                                        let _ = blocks_tx.send(BlockSolution {
+                                            device_id: device_id.clone(),
+                                            job_id: current_job.job_id.clone(),
+                                            nonce,
+                                            difficulty: validation.difficulty,
+                                        });
+                                    }
+                                    if validation.meets_pool_share {
+                                        let _ = shares_tx.send(FoundShare {
+                                            device_id: device_id.clone(),
+                                            job_id: current_job.job_id.clone(),
+                                            nonce,
+                                            difficulty: validation.difficulty,
+                                        });
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                error!("❌ {device_id}: unrecoverable engine error: {e}");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        self.devices.insert(
+            id,
+            ManagedDevice {
+                device,
+                hashes,
+                health,
+                job_tx,
+                stop,
+                handle,
+            },
+        );
+        Ok(())
+    }
+
+    /// Run (or reuse a cached) auto-tune sweep for `device` against `job`
+    /// and persist the winning settings, so a later [`GpuManager::add_device`]
+    /// for the same device+algorithm picks them up instead of falling back
+    /// to the engine's untuned suggestion. Builds its own throwaway engine
+    /// rather than touching a running device's.
+    pub async fn benchmark_device(&mut self, device: &OpenClDevice, job: &MiningJob) -> Result<TunedSettings, EngineError> {
+        let id = device.stable_id();
+        let mut engine = OpenClEngine::new(device.clone());
+        engine.initialize()?;
+        engine.load_algorithms(&[job.algo])?;
+
+        let settings = autotune(&mut engine, job, &id, &mut self.tuning).await?;
+        if let Err(e) = self.tuning.save(default_tuning_path()) {
+            warn!("⚠️  failed to persist tuning results to {:?}: {e}", default_tuning_path());
+        }
+        Ok(settings)
+    }
+
+    /// Push a new job to every running device, pre-empting whatever batch
+    /// each one is mid-way through. Every device shares one fresh nonce
+    /// allocator for the new job, same as when they were first added.
+    pub fn replace_job(&mut self, job: &MiningJob) {
+        let active = Arc::new(ActiveJob::new(job));
+        self.active_job = Some(active.clone());
+        for managed in self.devices.values() {
+            let _ = managed.job_tx.send(active.clone());
+        }
+    }
+
+    /// Stop and drop a device by its stable id — e.g. the user excludes the
+    /// display-attached GPU that crashes on the first job.
+    pub fn remove_device(&mut self, device_id: &str) {
+        if let Some(managed) = self.devices.remove(device_id) {
+            managed.stop.store(true, Ordering::Relaxed);
+            managed.handle.abort();
+        }
+    }
+
+    /// Record a pool's ack for a share or block solution previously drained
+    /// via [`GpuManager::try_recv_share`]/[`GpuManager::try_recv_block`], so
+    /// `stats()` reflects real accept/reject counts instead of reading zero
+    /// forever.
+    pub fn ack_submission(&self, device_id: &str, accepted: bool, reason: Option<RejectionReason>) {
+        self.stats.record_submission_ack(device_id, accepted, reason);
+    }
+
+    /// Next pool share found by any device, if one is queued.
+    pub fn try_recv_share(&mut self) -> Option<FoundShare> {
+        self.shares_rx.try_recv().ok()
+    }
+
+    /// Next network-target block solution found by any device, if one is
+    /// queued. Submitted to the node separately from pool shares.
+    pub fn try_recv_block(&mut self) -> Option<BlockSolution> {
+        self.blocks_rx.try_recv().ok()
+    }
+
+    /// Sum of every active device's running hash counter (hashes, not MH/s —
+    /// callers divide by elapsed time themselves, same as `gpu_test.rs`).
+    pub fn aggregate_hashes(&self) -> u64 {
+        self.devices.values().map(|d| d.hashes.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Current health of every device, for monitoring.
+    pub fn device_health(&self) -> HashMap<String, DeviceHealth> {
+        self.devices.iter().map(|(id, d)| (id.clone(), d.health.get())).collect()
+    }
+
+    pub fn device_ids(&self) -> Vec<String> {
+        self.devices.keys().cloned().collect()
+    }
+
+    pub fn device_info(&self, device_id: &str) -> Option<String> {
+        self.devices.get(device_id).map(|d| d.device.info_string())
+    }
+}
+
+impl Default for GpuManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,8 @@
+// File: src/miner/gpu/mod.rs
+
+//! GPU mining backends.
+
+pub mod benchmark;
+pub mod manager;
+pub mod opencl;
+pub mod watchdog;
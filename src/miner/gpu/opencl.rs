@@ -0,0 +1,331 @@
+// File: src/miner/gpu/opencl.rs
+
+//! OpenCL mining engine: one `OpenClEngine` per physical device, each
+//! claiming nonce ranges from a shared [`NonceAllocator`] rather than
+//! trusting the caller to hand it a non-overlapping offset.
+//!
+//! An engine can hold several compiled kernels at once (see
+//! [`OpenClEngine::load_algorithms`]) and switches between them per-job, the
+//! way sgminer-style miners do "on-the-fly" multi-algorithm switching,
+//! without tearing down the device context between jobs.
+
+use std::collections::HashMap;
+
+use opencl3::command_queue::{CommandQueue, CL_QUEUE_PROFILING_ENABLE};
+use opencl3::context::Context;
+use opencl3::device::{Device, CL_DEVICE_TYPE_GPU};
+use opencl3::kernel::{ExecuteKernel, Kernel};
+use opencl3::memory::{Buffer, CL_MEM_READ_WRITE};
+use opencl3::platform::get_platforms;
+use opencl3::program::Program;
+use opencl3::types::cl_ulong;
+use thiserror::Error;
+
+use crate::core::types::{Algorithm, Difficulty, MiningJob, ShareValidation};
+use crate::miner::gpu::benchmark::EngineMode;
+use crate::miner::nonce::NonceAllocator;
+
+fn kernel_source(algo: Algorithm) -> &'static str {
+    match algo {
+        Algorithm::Sha3x => include_str!("../../../kernels/sha3x.cl"),
+        Algorithm::Sha256d => include_str!("../../../kernels/sha256d.cl"),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EngineError {
+    #[error("no OpenCL platforms found")]
+    NoPlatforms,
+    #[error("OpenCL error: {0}")]
+    Cl(#[from] opencl3::error_codes::ClError),
+    #[error("engine used before initialize()")]
+    NotInitialized,
+    #[error("algorithm {0:?} not loaded; call load_algorithms() first")]
+    AlgorithmNotLoaded(Algorithm),
+    #[error("failed to build {0:?} kernel: {1}")]
+    KernelBuild(Algorithm, String),
+}
+
+/// A physical OpenCL-capable device, detected once at startup.
+#[derive(Clone)]
+pub struct OpenClDevice {
+    pub(crate) device: Device,
+}
+
+impl OpenClDevice {
+    /// Enumerate every GPU device across every OpenCL platform.
+    pub fn detect_devices() -> Result<Vec<OpenClDevice>, EngineError> {
+        let platforms = get_platforms()?;
+        if platforms.is_empty() {
+            return Err(EngineError::NoPlatforms);
+        }
+        let mut devices = Vec::new();
+        for platform in platforms {
+            for id in platform.get_devices(CL_DEVICE_TYPE_GPU)? {
+                devices.push(OpenClDevice { device: Device::new(id) });
+            }
+        }
+        Ok(devices)
+    }
+
+    /// Human-readable "device name (N CUs)" summary for logging.
+    pub fn info_string(&self) -> String {
+        let name = self.device.name().unwrap_or_else(|_| "unknown device".into());
+        let units = self.device.max_compute_units().unwrap_or(0);
+        format!("{name} ({units} CUs)")
+    }
+
+    /// A stable identifier for this device, suitable as a config key.
+    ///
+    /// Enumeration index is *not* stable across reboots (device order can
+    /// change), so per-device overrides must key off something tied to the
+    /// hardware: the PCI bus id when the vendor extension exposing it is
+    /// available, falling back to a string of name/vendor/memory size (stable
+    /// as long as the machine doesn't have two otherwise-identical cards,
+    /// in which case they're interchangeable anyway).
+    pub fn stable_id(&self) -> String {
+        if let Ok(pci_bus_id) = self.device.pci_bus_id_amd() {
+            return format!("pci-{pci_bus_id:04x}");
+        }
+        if let Ok(pci_bus_id) = self.device.pci_bus_id_nv() {
+            return format!("pci-{pci_bus_id:04x}");
+        }
+
+        let name = self.device.name().unwrap_or_default();
+        let vendor = self.device.vendor().unwrap_or_default();
+        let global_mem = self.device.global_mem_size().unwrap_or(0);
+        format!("fallback-{name}-{vendor}-{global_mem}")
+    }
+}
+
+/// Result of one [`OpenClEngine::mine`] batch.
+#[derive(Debug, Clone, Copy)]
+pub struct MineOutcome {
+    pub found_nonce: Option<u64>,
+    pub hashes_processed: u32,
+    /// `None` when `found_nonce` is `None` — there's nothing to validate.
+    pub validation: Option<ShareValidation>,
+    /// True when this zero-work outcome came from [`crate::miner::gpu::watchdog::Watchdog`]
+    /// mid-recovery rather than the nonce allocator being genuinely
+    /// exhausted — callers should retry immediately instead of parking
+    /// until the next job, which may never come.
+    pub recovering: bool,
+}
+
+impl MineOutcome {
+    /// A zero-work outcome because the nonce allocator for this job is
+    /// exhausted; the caller should request a new job instead of re-scanning.
+    pub fn zero() -> Self {
+        Self {
+            found_nonce: None,
+            hashes_processed: 0,
+            validation: None,
+            recovering: false,
+        }
+    }
+
+    /// A zero-work outcome because the engine is mid-recovery, not because
+    /// the nonce space ran out.
+    pub fn recovering() -> Self {
+        Self {
+            recovering: true,
+            ..Self::zero()
+        }
+    }
+}
+
+/// A compiled, ready-to-run program for a single [`Algorithm`] on one
+/// device.
+struct AlgorithmProgram {
+    #[allow(dead_code)]
+    program: Program,
+    kernel: Kernel,
+}
+
+/// A mining engine bound to one OpenCL device.
+///
+/// Call [`OpenClEngine::load_algorithms`] once after [`initialize`](Self::initialize)
+/// for every algorithm the current pool session might assign, then
+/// [`OpenClEngine::mine`] dispatches to whichever kernel `job.algo` names,
+/// switching kernels between jobs without rebuilding the context.
+pub struct OpenClEngine {
+    device: OpenClDevice,
+    context: Option<Context>,
+    queue: Option<CommandQueue>,
+    algorithms: HashMap<Algorithm, AlgorithmProgram>,
+    active_algo: Option<Algorithm>,
+    mode: EngineMode,
+}
+
+impl OpenClEngine {
+    pub fn new(device: OpenClDevice) -> Self {
+        Self {
+            device,
+            context: None,
+            queue: None,
+            algorithms: HashMap::new(),
+            active_algo: None,
+            mode: EngineMode::Mining,
+        }
+    }
+
+    /// Whether this engine is currently sweeping settings for the
+    /// auto-tuner or running a live mining job.
+    pub fn mode(&self) -> EngineMode {
+        self.mode
+    }
+
+    /// Set by [`crate::miner::gpu::benchmark::autotune`] for the duration of
+    /// a sweep so logs/UIs can't mistake benchmark throughput for real
+    /// share-producing hashrate, then reset to `Mining` once it's done.
+    pub fn set_mode(&mut self, mode: EngineMode) {
+        self.mode = mode;
+    }
+
+    /// Build the OpenCL context/queue and compile the SHA3x kernel, since
+    /// that's the algorithm Tari pools assign on the first job.
+    pub fn initialize(&mut self) -> Result<(), EngineError> {
+        let context = Context::from_device(&self.device.device)?;
+        let queue = CommandQueue::create_default(&context, CL_QUEUE_PROFILING_ENABLE)?;
+        self.context = Some(context);
+        self.queue = Some(queue);
+        self.load_algorithms(&[Algorithm::Sha3x])
+    }
+
+    /// Compile and cache a program for each algorithm in `algos` that isn't
+    /// already loaded. Requires [`initialize`](Self::initialize) to have run
+    /// (context/queue must exist), but can be called again later, mid-session,
+    /// to pick up an algorithm the pool just switched to.
+    pub fn load_algorithms(&mut self, algos: &[Algorithm]) -> Result<(), EngineError> {
+        let context = self.context.as_ref().ok_or(EngineError::NotInitialized)?;
+        for &algo in algos {
+            if self.algorithms.contains_key(&algo) {
+                continue;
+            }
+            let program = Program::create_and_build_from_source(context, kernel_source(algo), "")
+                .map_err(|log| EngineError::KernelBuild(algo, log.to_string()))?;
+            let kernel = Kernel::create(&program, algo.kernel_fn_name())?;
+            self.algorithms.insert(algo, AlgorithmProgram { program, kernel });
+        }
+        if self.active_algo.is_none() {
+            self.active_algo = algos.first().copied();
+        }
+        Ok(())
+    }
+
+    /// Algorithm the engine last dispatched `mine()` to; before the first
+    /// dispatch, the first algorithm passed to [`Self::load_algorithms`].
+    pub fn active_algo(&self) -> Option<Algorithm> {
+        self.active_algo
+    }
+
+    /// A reasonable starting batch size for this device running `algo`,
+    /// refined later by the auto-tuning benchmark mode. Heavier kernels
+    /// (more registers/local memory per work-item) get a smaller suggested
+    /// batch than SHA3x's.
+    pub fn get_suggested_batch_size(&self, algo: Algorithm) -> u32 {
+        let compute_units = self.device.device.max_compute_units().unwrap_or(16);
+        let per_cu = match algo {
+            Algorithm::Sha3x => 256 * 64,
+            Algorithm::Sha256d => 256 * 48,
+        };
+        compute_units * per_cu
+    }
+
+    /// Claim one batch of nonces from `allocator`, run `job.algo`'s kernel
+    /// over it (switching the engine's active algorithm first if the job
+    /// calls for a different one than last time), and validate any found
+    /// nonce's difficulty against both the pool share target
+    /// (`job.target_difficulty`) and the network block target
+    /// (`job.target`), so the caller knows which to submit.
+    ///
+    /// Returns a zero-work [`MineOutcome`] once `allocator` is exhausted so
+    /// the caller knows to request a new job instead of re-scanning.
+    pub async fn mine(
+        &mut self,
+        job: &MiningJob,
+        allocator: &NonceAllocator,
+        batch_size: u32,
+    ) -> Result<MineOutcome, EngineError> {
+        self.mine_with_worksize(job, allocator, batch_size, None).await
+    }
+
+    /// Same as [`Self::mine`], but pins the OpenCL local work-group size
+    /// instead of letting the driver pick one. Used by the benchmark
+    /// auto-tuner to sweep worksize alongside batch size; regular mining
+    /// just calls `mine()`, which passes `None` here.
+    pub async fn mine_with_worksize(
+        &mut self,
+        job: &MiningJob,
+        allocator: &NonceAllocator,
+        batch_size: u32,
+        local_worksize: Option<usize>,
+    ) -> Result<MineOutcome, EngineError> {
+        let context = self.context.as_ref().ok_or(EngineError::NotInitialized)?;
+        let queue = self.queue.as_ref().ok_or(EngineError::NotInitialized)?;
+        let algorithm = self
+            .algorithms
+            .get(&job.algo)
+            .ok_or(EngineError::AlgorithmNotLoaded(job.algo))?;
+
+        // Checked before claiming nonces: an unloaded algorithm is a config
+        // error that should surface immediately, not burn a batch's worth of
+        // the nonce space before failing.
+        let Some((start_nonce, claimed)) = allocator.claim(batch_size) else {
+            // Nonce space for this job is exhausted; caller should request a new one.
+            return Ok(MineOutcome::zero());
+        };
+
+        self.active_algo = Some(job.algo);
+
+        let mut result_buffer =
+            Buffer::<cl_ulong>::create(context, CL_MEM_READ_WRITE, 2, std::ptr::null_mut())?;
+
+        // Seed the buffer with a not-found sentinel before dispatch: the
+        // buffer is freshly allocated device memory, not zeroed, and a
+        // kernel that doesn't hit on this batch (or doesn't write the slot
+        // at all) must not leave us reading back whatever garbage happened
+        // to be there as a fabricated found nonce.
+        const NOT_FOUND: [cl_ulong; 2] = [u64::MAX, 0];
+        let seed_event = unsafe { queue.enqueue_write_buffer(&mut result_buffer, opencl3::types::CL_BLOCKING, 0, &NOT_FOUND, &[])? };
+        seed_event.wait()?;
+
+        let event = unsafe {
+            let mut exec = ExecuteKernel::new(&algorithm.kernel);
+            exec.set_arg(&start_nonce)
+                .set_arg(&claimed)
+                .set_arg(&mut result_buffer)
+                .set_global_work_size(claimed as usize);
+            if let Some(local_worksize) = local_worksize {
+                exec.set_local_work_size(local_worksize);
+            }
+            exec.enqueue_nd_range(queue)?
+        };
+        event.wait()?;
+
+        let mut result = [0u64; 2];
+        let read_event = unsafe { queue.enqueue_read_buffer(&result_buffer, opencl3::types::CL_BLOCKING, 0, &mut result, &[])? };
+        read_event.wait()?;
+
+        let [found_nonce_raw, best_difficulty_raw] = result;
+        let found_nonce = if found_nonce_raw == u64::MAX { None } else { Some(found_nonce_raw) };
+
+        let validation = found_nonce.map(|_| {
+            let difficulty = Difficulty::new(best_difficulty_raw).unwrap_or(Difficulty::MIN);
+            let pool_target = Difficulty::new(job.target_difficulty).unwrap_or(Difficulty::MIN);
+            let network_target = job.target.map(|t| Difficulty::from_target(&t));
+            ShareValidation {
+                difficulty,
+                meets_pool_share: difficulty.meets(pool_target),
+                meets_network_target: network_target.is_some_and(|nt| difficulty.meets(nt)),
+            }
+        });
+
+        Ok(MineOutcome {
+            found_nonce,
+            hashes_processed: claimed,
+            validation,
+            recovering: false,
+        })
+    }
+}
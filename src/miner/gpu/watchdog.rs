@@ -0,0 +1,164 @@
+// File: src/miner/gpu/watchdog.rs
+
+//! Per-device watchdog: wraps `OpenClEngine::mine` with a timeout and
+//! recovers a hung/erroring device by tearing down and re-`initialize()`ing
+//! its context in place, without taking down the other devices or the rig
+//! process.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::timeout;
+use tracing::{error, info, warn};
+
+use crate::core::types::MiningJob;
+use crate::miner::gpu::opencl::{EngineError, MineOutcome, OpenClDevice, OpenClEngine};
+use crate::miner::nonce::NonceAllocator;
+
+/// How long a single `mine()` batch is allowed to run before it's treated
+/// as a hung kernel/readback.
+const MINE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Consecutive recovery attempts allowed before a device is declared dead
+/// instead of retried again.
+const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// Externally-visible health of one device, for monitoring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceHealth {
+    Healthy,
+    Recovering,
+    Dead,
+}
+
+impl DeviceHealth {
+    fn as_u8(self) -> u8 {
+        match self {
+            DeviceHealth::Healthy => 0,
+            DeviceHealth::Recovering => 1,
+            DeviceHealth::Dead => 2,
+        }
+    }
+
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => DeviceHealth::Healthy,
+            1 => DeviceHealth::Recovering,
+            _ => DeviceHealth::Dead,
+        }
+    }
+}
+
+/// Lock-free health flag a [`Watchdog`] updates and anything else (stats,
+/// an HTTP status endpoint) can poll without synchronizing with the mining
+/// loop.
+#[derive(Debug)]
+pub struct HealthState(AtomicU8);
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self(AtomicU8::new(DeviceHealth::Healthy.as_u8()))
+    }
+
+    pub fn get(&self) -> DeviceHealth {
+        DeviceHealth::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, health: DeviceHealth) {
+        self.0.store(health.as_u8(), Ordering::Relaxed);
+    }
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Guards one device's mining loop: every `mine()` call goes through
+/// [`Watchdog::guarded_mine`] instead of being called directly, so a hang
+/// or OpenCL error recovers that device in place rather than propagating.
+pub struct Watchdog {
+    device: OpenClDevice,
+    health: Arc<HealthState>,
+    consecutive_failures: u32,
+}
+
+impl Watchdog {
+    pub fn new(device: OpenClDevice) -> Self {
+        Self {
+            device,
+            health: Arc::new(HealthState::new()),
+            consecutive_failures: 0,
+        }
+    }
+
+    /// Shared handle other subsystems can poll for this device's health.
+    pub fn health(&self) -> Arc<HealthState> {
+        self.health.clone()
+    }
+
+    /// Run one guarded batch. On timeout or OpenCL error this rebuilds
+    /// `engine`'s context in place and returns zero-work so the caller's
+    /// loop just continues on the next iteration (resuming on whatever job
+    /// it's given next) instead of propagating the failure up and taking
+    /// the whole device offline. If re-initializing fails, that error is
+    /// returned; once the device is marked [`DeviceHealth::Dead`], every
+    /// further failure returns zero-work without rebuilding.
+    pub async fn guarded_mine(
+        &mut self,
+        engine: &mut OpenClEngine,
+        job: &MiningJob,
+        allocator: &NonceAllocator,
+        batch_size: u32,
+    ) -> Result<MineOutcome, EngineError> {
+        match timeout(MINE_TIMEOUT, engine.mine(job, allocator, batch_size)).await {
+            Ok(Ok(result)) => {
+                self.consecutive_failures = 0;
+                self.health.set(DeviceHealth::Healthy);
+                Ok(result)
+            }
+            Ok(Err(e)) => {
+                error!("❌ {}: mining error, recovering: {e}", self.device.info_string());
+                self.recover(engine, job).await
+            }
+            Err(_) => {
+                warn!(
+                    "⏱️  {}: mine() did not return within {MINE_TIMEOUT:?}, recovering",
+                    self.device.info_string()
+                );
+                self.recover(engine, job).await
+            }
+        }
+    }
+
+    async fn recover(&mut self, engine: &mut OpenClEngine, job: &MiningJob) -> Result<MineOutcome, EngineError> {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures > MAX_CONSECUTIVE_FAILURES {
+            self.health.set(DeviceHealth::Dead);
+            error!(
+                "💀 {}: giving up after {} consecutive recovery attempts",
+                self.device.info_string(),
+                self.consecutive_failures
+            );
+            return Ok(MineOutcome::zero());
+        }
+
+        self.health.set(DeviceHealth::Recovering);
+        *engine = OpenClEngine::new(self.device.clone());
+        // `initialize()` only compiles the Sha3x kernel; without reloading
+        // the job's actual algorithm here too, a device recovering mid-session
+        // on Sha256d would come back up silently stuck on the wrong kernel.
+        match engine.initialize().and_then(|()| engine.load_algorithms(&[job.algo])) {
+            Ok(()) => {
+                info!("✅ {}: recovered", self.device.info_string());
+                Ok(MineOutcome::recovering())
+            }
+            Err(e) => {
+                error!("❌ {}: re-initialize failed: {e}", self.device.info_string());
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// File: src/miner/mod.rs
+
+//! Mining engines and the infrastructure they share (nonce allocation, GPU
+//! device management).
+
+pub mod gpu;
+pub mod nonce;
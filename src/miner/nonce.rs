@@ -0,0 +1,110 @@
+// File: src/miner/nonce.rs
+
+//! Lock-free nonce allocation shared across GPU batches and devices.
+//!
+//! Every `OpenClEngine::mine` call claims a disjoint slice of the nonce space
+//! from a `NonceAllocator` via a single `fetch_add`, rather than each caller
+//! tracking (and guessing at) its own offset. One allocator can be shared
+//! (`Arc<NonceAllocator>`) across every engine mining the same job so that
+//! many GPUs never scan overlapping ranges or leave gaps between batches.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Nonce space width to use for jobs that carry a pool extranonce (the "XN"
+/// case, e.g. LuckyPool): the pool owns the high bytes, so the miner must
+/// stay within the low 6 bytes it actually controls.
+pub const EXTRANONCE_NONCE_WIDTH: u64 = 0x0001_0000_0000_0000; // 2^48, i.e. nonces [0, 0xFFFF_FFFF_FFFF]
+
+/// Nonce space width for jobs with no extranonce, where the miner owns the
+/// full 64-bit nonce field.
+pub const FULL_NONCE_WIDTH: u64 = u64::MAX;
+
+/// A `fetch_add`-based nonce cursor bounded to `[0, width)`.
+///
+/// `claim` never hands out the same nonce twice and never wraps silently:
+/// once the cursor would exceed `width` it returns `None` so the caller
+/// requests a new job instead of re-scanning nonces the pool has already
+/// seen shares for.
+#[derive(Debug)]
+pub struct NonceAllocator {
+    next: AtomicU64,
+    width: u64,
+}
+
+impl NonceAllocator {
+    /// Create an allocator bounded to `[0, width)`.
+    pub fn new(width: u64) -> Self {
+        Self {
+            next: AtomicU64::new(0),
+            width,
+        }
+    }
+
+    /// Create an allocator sized for `job`: [`EXTRANONCE_NONCE_WIDTH`] when
+    /// the job carries a pool extranonce, [`FULL_NONCE_WIDTH`] otherwise.
+    pub fn for_job(job: &crate::core::types::MiningJob) -> Self {
+        if job.has_extranonce() {
+            Self::new(EXTRANONCE_NONCE_WIDTH)
+        } else {
+            Self::new(FULL_NONCE_WIDTH)
+        }
+    }
+
+    /// Atomically claim the next `batch_size` nonces.
+    ///
+    /// Returns `Some((start, len))` where `len <= batch_size` (the final
+    /// batch before exhaustion may be shorter), or `None` once the nonce
+    /// space for this job has been fully claimed.
+    pub fn claim(&self, batch_size: u32) -> Option<(u64, u32)> {
+        let batch_size = batch_size as u64;
+        let start = self.next.fetch_add(batch_size, Ordering::Relaxed);
+        if start >= self.width {
+            return None;
+        }
+        let len = batch_size.min(self.width - start);
+        Some((start, len as u32))
+    }
+
+    /// Reset the cursor to zero, e.g. when a new `MiningJob` replaces the
+    /// one this allocator was created for.
+    pub fn reset(&self) {
+        self.next.store(0, Ordering::Relaxed);
+    }
+
+    /// Total width of the nonce space this allocator hands out.
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_are_disjoint_and_contiguous() {
+        let allocator = NonceAllocator::new(100);
+        let (a_start, a_len) = allocator.claim(40).unwrap();
+        let (b_start, b_len) = allocator.claim(40).unwrap();
+        assert_eq!((a_start, a_len), (0, 40));
+        assert_eq!((b_start, b_len), (40, 40));
+    }
+
+    #[test]
+    fn final_batch_is_truncated_then_exhausted() {
+        let allocator = NonceAllocator::new(100);
+        allocator.claim(90).unwrap();
+        let (start, len) = allocator.claim(40).unwrap();
+        assert_eq!((start, len), (90, 10));
+        assert!(allocator.claim(1).is_none());
+    }
+
+    #[test]
+    fn reset_reopens_the_space() {
+        let allocator = NonceAllocator::new(10);
+        allocator.claim(10).unwrap();
+        assert!(allocator.claim(1).is_none());
+        allocator.reset();
+        assert_eq!(allocator.claim(5), Some((0, 5)));
+    }
+}
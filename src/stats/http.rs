@@ -0,0 +1,54 @@
+// File: src/stats/http.rs
+
+//! A deliberately minimal HTTP/JSON endpoint for the stats snapshot: no
+//! router, no framework, just enough for an external dashboard to poll
+//! `GET /stats` and get the current [`StatsSnapshot`](super::StatsSnapshot)
+//! back as JSON.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use tracing::{error, info, warn};
+
+use super::Stats;
+
+/// Start the stats HTTP server on a background OS thread and return once
+/// it's bound; the thread runs for the lifetime of the process.
+pub fn spawn(stats: Stats, addr: &str) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let bound_addr = listener.local_addr()?;
+    info!("📡 Stats HTTP endpoint listening on http://{bound_addr}/stats");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => handle_connection(stream, &stats),
+                Err(e) => warn!("stats endpoint: accept error: {e}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, stats: &Stats) {
+    // The only resource this server has is `/stats`, so the request itself
+    // doesn't need parsing — just drain it off the socket before replying.
+    let mut discard = [0u8; 1024];
+    let _ = stream.read(&mut discard);
+
+    let body = match serde_json::to_string(&stats.snapshot()) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("stats endpoint: failed to serialize snapshot: {e}");
+            return;
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
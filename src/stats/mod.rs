@@ -0,0 +1,245 @@
+// File: src/stats/mod.rs
+
+//! Per-device and aggregate mining telemetry: accepted/rejected shares,
+//! rejection reasons, rolling hashrate, best difficulty seen, and uptime —
+//! the way established miners report per-thread ACC/REJ counters through
+//! their API, instead of the ad-hoc `info!("... MH/s")` logging
+//! `gpu_test.rs` does today.
+
+pub mod http;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::core::types::Difficulty;
+
+/// Why a submitted share was rejected, for grouping in the snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectionReason {
+    LowDifficulty,
+    StaleJob,
+    DuplicateShare,
+    Other,
+}
+
+impl RejectionReason {
+    fn label(self) -> &'static str {
+        match self {
+            RejectionReason::LowDifficulty => "low_difficulty",
+            RejectionReason::StaleJob => "stale_job",
+            RejectionReason::DuplicateShare => "duplicate_share",
+            RejectionReason::Other => "other",
+        }
+    }
+}
+
+/// Exponentially-weighted rolling hashrate over a fixed window, updated
+/// incrementally as hashes are reported rather than by re-summing a history
+/// buffer on every read.
+#[derive(Debug, Clone, Copy)]
+struct Ewma {
+    window: Duration,
+    hashes_per_sec: f64,
+    last_update: Option<Instant>,
+}
+
+impl Ewma {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            hashes_per_sec: 0.0,
+            last_update: None,
+        }
+    }
+
+    fn record(&mut self, hashes: u64, now: Instant) {
+        let Some(last) = self.last_update else {
+            self.last_update = Some(now);
+            return;
+        };
+        let dt = now.duration_since(last).as_secs_f64();
+        if dt <= 0.0 {
+            return;
+        }
+        let instantaneous = hashes as f64 / dt;
+        // Smoothing factor grows with dt relative to the window: a sample
+        // spanning the full window fully replaces the average, a quick
+        // sample barely nudges it.
+        let alpha = (dt / self.window.as_secs_f64()).min(1.0);
+        self.hashes_per_sec += alpha * (instantaneous - self.hashes_per_sec);
+        self.last_update = Some(now);
+    }
+}
+
+struct DeviceStats {
+    accepted: u64,
+    rejected: u64,
+    rejection_reasons: HashMap<RejectionReason, u64>,
+    best_difficulty: Option<Difficulty>,
+    started_at: Instant,
+    hashrate_10s: Ewma,
+    hashrate_60s: Ewma,
+    hashrate_15m: Ewma,
+}
+
+impl DeviceStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            accepted: 0,
+            rejected: 0,
+            rejection_reasons: HashMap::new(),
+            best_difficulty: None,
+            started_at: now,
+            hashrate_10s: Ewma::new(Duration::from_secs(10)),
+            hashrate_60s: Ewma::new(Duration::from_secs(60)),
+            hashrate_15m: Ewma::new(Duration::from_secs(15 * 60)),
+        }
+    }
+}
+
+/// One device's stats at the moment [`Stats::snapshot`] was called.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSnapshot {
+    pub device_id: String,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub rejection_reasons: HashMap<String, u64>,
+    pub best_difficulty: Option<u64>,
+    pub uptime_secs: u64,
+    pub hashrate_10s_mhs: f64,
+    pub hashrate_60s_mhs: f64,
+    pub hashrate_15m_mhs: f64,
+}
+
+/// Rig-wide stats at the moment [`Stats::snapshot`] was called.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSnapshot {
+    pub devices: Vec<DeviceSnapshot>,
+    pub aggregate_accepted: u64,
+    pub aggregate_rejected: u64,
+    pub aggregate_hashrate_10s_mhs: f64,
+}
+
+/// Thread-safe stats collector, cheap to clone (an `Arc` internally) so
+/// every device's mining task and an optional HTTP endpoint can share one
+/// instance.
+#[derive(Clone)]
+pub struct Stats {
+    inner: std::sync::Arc<Mutex<HashMap<String, DeviceStats>>>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self {
+            inner: std::sync::Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Record `hashes` processed by `device_id` just now, feeding all three
+    /// rolling hashrate windows.
+    pub fn record_hashes(&self, device_id: &str, hashes: u64) {
+        let now = Instant::now();
+        let mut devices = self.inner.lock().expect("stats mutex poisoned");
+        let device = devices.entry(device_id.to_string()).or_insert_with(|| DeviceStats::new(now));
+        device.hashrate_10s.record(hashes, now);
+        device.hashrate_60s.record(hashes, now);
+        device.hashrate_15m.record(hashes, now);
+    }
+
+    /// Record the best difficulty a device has found, keeping the running
+    /// maximum.
+    pub fn record_difficulty(&self, device_id: &str, difficulty: Difficulty) {
+        let mut devices = self.inner.lock().expect("stats mutex poisoned");
+        let device = devices
+            .entry(device_id.to_string())
+            .or_insert_with(|| DeviceStats::new(Instant::now()));
+        device.best_difficulty = Some(match device.best_difficulty {
+            Some(best) if best >= difficulty => best,
+            _ => difficulty,
+        });
+    }
+
+    /// Record a pool's ack for a submitted share: accepted, or rejected
+    /// with a reason.
+    pub fn record_submission_ack(&self, device_id: &str, accepted: bool, reason: Option<RejectionReason>) {
+        let mut devices = self.inner.lock().expect("stats mutex poisoned");
+        let device = devices
+            .entry(device_id.to_string())
+            .or_insert_with(|| DeviceStats::new(Instant::now()));
+        if accepted {
+            device.accepted += 1;
+        } else {
+            device.rejected += 1;
+            *device.rejection_reasons.entry(reason.unwrap_or(RejectionReason::Other)).or_insert(0) += 1;
+        }
+    }
+
+    /// Snapshot every device's stats plus rig-wide aggregates.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let devices = self.inner.lock().expect("stats mutex poisoned");
+        let now = Instant::now();
+
+        let device_snapshots: Vec<DeviceSnapshot> = devices
+            .iter()
+            .map(|(device_id, stats)| DeviceSnapshot {
+                device_id: device_id.clone(),
+                accepted: stats.accepted,
+                rejected: stats.rejected,
+                rejection_reasons: stats
+                    .rejection_reasons
+                    .iter()
+                    .map(|(reason, count)| (reason.label().to_string(), *count))
+                    .collect(),
+                best_difficulty: stats.best_difficulty.map(Difficulty::as_u64),
+                uptime_secs: now.duration_since(stats.started_at).as_secs(),
+                hashrate_10s_mhs: stats.hashrate_10s.hashes_per_sec / 1_000_000.0,
+                hashrate_60s_mhs: stats.hashrate_60s.hashes_per_sec / 1_000_000.0,
+                hashrate_15m_mhs: stats.hashrate_15m.hashes_per_sec / 1_000_000.0,
+            })
+            .collect();
+
+        StatsSnapshot {
+            aggregate_accepted: device_snapshots.iter().map(|d| d.accepted).sum(),
+            aggregate_rejected: device_snapshots.iter().map(|d| d.rejected).sum(),
+            aggregate_hashrate_10s_mhs: device_snapshots.iter().map(|d| d.hashrate_10s_mhs).sum(),
+            devices: device_snapshots,
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_accepted_and_rejected_counts() {
+        let stats = Stats::new();
+        stats.record_submission_ack("gpu0", true, None);
+        stats.record_submission_ack("gpu0", false, Some(RejectionReason::StaleJob));
+
+        let snapshot = stats.snapshot();
+        let gpu0 = &snapshot.devices[0];
+        assert_eq!(gpu0.accepted, 1);
+        assert_eq!(gpu0.rejected, 1);
+        assert_eq!(gpu0.rejection_reasons.get("stale_job"), Some(&1));
+    }
+
+    #[test]
+    fn keeps_running_max_difficulty() {
+        let stats = Stats::new();
+        stats.record_difficulty("gpu0", Difficulty::new(100).unwrap());
+        stats.record_difficulty("gpu0", Difficulty::new(50).unwrap());
+        stats.record_difficulty("gpu0", Difficulty::new(200).unwrap());
+
+        assert_eq!(stats.snapshot().devices[0].best_difficulty, Some(200));
+    }
+}